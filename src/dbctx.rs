@@ -0,0 +1,131 @@
+use rusqlite::{params, Connection};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// SQLite-backed cache of compiled bytecode, keyed by the full `owner/repo` +
+/// contract path + file SHA (the same SHA GitHub's contents API returns in
+/// `RepoContent::sha`). The key is `owner/repo`, not the bare repo name --
+/// generic names like `contracts`/`core` recur across unrelated sponsors'
+/// repos, and a bare-name key would let their bytecode/history collide.
+/// Lets a re-run skip `forge inspect` for any contract whose source hasn't
+/// changed since the last pass, and keeps a timestamped history of bytecode
+/// per contract so later runs can be compared against earlier ones.
+pub struct Db {
+    conn: Mutex<Connection>,
+}
+
+impl Db {
+    /// Opens (creating if necessary) the SQLite database at `path` and
+    /// ensures the schema exists.
+    pub fn open(path: &str) -> rusqlite::Result<Db> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS contests (
+                id INTEGER PRIMARY KEY,
+                repo TEXT NOT NULL UNIQUE
+            );
+            CREATE TABLE IF NOT EXISTS contracts (
+                id INTEGER PRIMARY KEY,
+                contest_id INTEGER NOT NULL REFERENCES contests(id),
+                path TEXT NOT NULL,
+                sha TEXT NOT NULL,
+                UNIQUE(contest_id, path)
+            );
+            CREATE TABLE IF NOT EXISTS bytecode_records (
+                id INTEGER PRIMARY KEY,
+                contract_id INTEGER NOT NULL REFERENCES contracts(id),
+                bytecode TEXT NOT NULL,
+                recorded_at INTEGER NOT NULL
+            );",
+        )?;
+        Ok(Db { conn: Mutex::new(conn) })
+    }
+
+    fn contest_id(conn: &Connection, repo_key: &str) -> rusqlite::Result<i64> {
+        conn.execute(
+            "INSERT INTO contests (repo) VALUES (?1) ON CONFLICT(repo) DO NOTHING",
+            params![repo_key],
+        )?;
+        conn.query_row("SELECT id FROM contests WHERE repo = ?1", params![repo_key], |row| row.get(0))
+    }
+
+    /// Returns the most recently recorded bytecode for `(repo_key, path)` if
+    /// the contract's stored SHA still matches `sha` -- i.e. its source
+    /// hasn't changed since the bytecode was compiled.
+    pub fn cached_bytecode(&self, repo_key: &str, path: &str, sha: &str) -> rusqlite::Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT b.bytecode
+             FROM contracts c
+             JOIN contests t ON t.id = c.contest_id
+             JOIN bytecode_records b ON b.contract_id = c.id
+             WHERE t.repo = ?1 AND c.path = ?2 AND c.sha = ?3
+             ORDER BY b.recorded_at DESC LIMIT 1",
+            params![repo_key, path, sha],
+            |row| row.get(0),
+        );
+        match result {
+            Ok(bytecode) => Ok(Some(bytecode)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the most recently recorded bytecode for `(repo_key, path)`,
+    /// regardless of whether its SHA still matches -- used as the diff
+    /// baseline when a contract's source has changed since the last run.
+    pub fn latest_bytecode(&self, repo_key: &str, path: &str) -> rusqlite::Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT b.bytecode
+             FROM contracts c
+             JOIN contests t ON t.id = c.contest_id
+             JOIN bytecode_records b ON b.contract_id = c.id
+             WHERE t.repo = ?1 AND c.path = ?2
+             ORDER BY b.recorded_at DESC LIMIT 1",
+            params![repo_key, path],
+            |row| row.get(0),
+        );
+        match result {
+            Ok(bytecode) => Ok(Some(bytecode)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns every contract path currently recorded for `repo_key`, so a
+    /// caller can spot contracts that disappeared between runs.
+    pub fn known_paths(&self, repo_key: &str) -> rusqlite::Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT c.path FROM contracts c
+             JOIN contests t ON t.id = c.contest_id
+             WHERE t.repo = ?1",
+        )?;
+        stmt.query_map(params![repo_key], |row| row.get(0))?.collect()
+    }
+
+    /// Records a freshly compiled `bytecode` for `(repo_key, path, sha)`:
+    /// updates the contract's current SHA and appends a new timestamped
+    /// history row.
+    pub fn record_bytecode(&self, repo_key: &str, path: &str, sha: &str, bytecode: &str) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let contest_id = Self::contest_id(&conn, repo_key)?;
+        conn.execute(
+            "INSERT INTO contracts (contest_id, path, sha) VALUES (?1, ?2, ?3)
+             ON CONFLICT(contest_id, path) DO UPDATE SET sha = excluded.sha",
+            params![contest_id, path, sha],
+        )?;
+        let contract_id: i64 = conn.query_row(
+            "SELECT id FROM contracts WHERE contest_id = ?1 AND path = ?2",
+            params![contest_id, path],
+            |row| row.get(0),
+        )?;
+        let recorded_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+        conn.execute(
+            "INSERT INTO bytecode_records (contract_id, bytecode, recorded_at) VALUES (?1, ?2, ?3)",
+            params![contract_id, bytecode, recorded_at],
+        )?;
+        Ok(())
+    }
+}
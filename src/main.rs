@@ -12,13 +12,24 @@ use std::path::Path;
 use tokio::process::Command; 
 use tokio::fs::remove_dir_all;
 use std::io::{Error as IoError, ErrorKind};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::time::{sleep, Duration};
-use git2::Repository;
+use tokio::sync::Semaphore;
+use git2::{FetchOptions, build::RepoBuilder};
+use diffy::create_patch;
 use headless_chrome::{Browser, protocol::cdp::Page::CaptureScreenshotFormatOption, Element};
 use headless_chrome::protocol::cdp::Page;
 
 use serde::Deserialize;
 
+mod dbctx;
+use dbctx::Db;
+mod webhook;
+
+/// Path to the SQLite cache of contests/contracts/bytecode history.
+const DB_PATH: &str = "./audits.db";
+
 #[derive(Debug, Deserialize)]
 struct Links {
     #[serde(rename = "self")]
@@ -50,17 +61,47 @@ struct RepoContent {
 
 type ResponseContent = Option<Vec<RepoContent>>;
 
+/// A contest's GitHub repo, optionally pinned to the exact commit or tag the
+/// audit was scoped to (parsed from a `#<sha>` fragment on the dropdown
+/// href). When unset, the clone step falls back to the default branch HEAD.
+#[derive(Debug, Clone)]
+struct Contest {
+    repo_url: String,
+    pinned_ref: Option<String>,
+}
+
+/// Maximum number of repos that may be cloned/compiled at the same time.
+/// The permit covers git clone, `forge compile` and the per-contract
+/// `forge inspect` walk. A repo's top-level GitHub contents request is made
+/// before the permit is taken; the subdirectory listings fetched during the
+/// contract walk happen while it is held.
+const MAX_CONCURRENT_OPS: usize = 4;
+
+/// Hands out a unique suffix for each repo's working directory. Needed now
+/// that repos clone/compile concurrently: two contests can share a trailing
+/// path segment (two orgs both naming their repo `core`, or the same repo
+/// showing up in both the `active` and `upcoming` lists), and without a
+/// unique directory per task one clone/compile could stomp on another's
+/// in-progress checkout.
+static NEXT_CLONE_ID: AtomicU64 = AtomicU64::new(0);
+
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {   
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // dotenv().ok();
 
+    let db = Arc::new(Db::open(DB_PATH)?);
+
+    if env::args().any(|arg| arg == "--serve") {
+        return run_webhook_server(db).await;
+    }
+
     let mut active_contests = get_contests("active").await?;
     let mut upcoming_contests = get_contests("upcoming").await?;
     active_contests.append(&mut upcoming_contests);
 
     println!("Contests: {:#?}", &active_contests);
 
-    match process(&active_contests).await {
+    match process(&active_contests, &db).await {
         Ok(all_results) => {
             println!("Done: {}", all_results.len());
         },
@@ -69,6 +110,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Runs the long-running webhook server instead of the one-shot Code4rena
+/// scrape: listens for GitHub push events and re-audits just the pushed
+/// repo, keeping the bytecode index live. Started with `--serve`; requires
+/// `WEBHOOK_SECRETS` (comma-separated pre-shared keys) to be set.
+async fn run_webhook_server(db: Arc<Db>) -> Result<(), Box<dyn std::error::Error>> {
+    let webhook_secrets: Vec<String> = env::var("WEBHOOK_SECRETS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if webhook_secrets.is_empty() {
+        eprintln!("WEBHOOK_SECRETS is not set; refusing to start the webhook server without a pre-shared key.");
+        return Ok(());
+    }
+
+    let addr = env::var("WEBHOOK_ADDR").unwrap_or_else(|_| "0.0.0.0:3000".to_string());
+    let state = webhook::WebhookState {
+        repo_client: reqwest::Client::new(),
+        clone_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_OPS)),
+        db,
+        webhook_secrets,
+    };
+    webhook::run(&addr, state).await?;
+    Ok(())
+}
+
 /// Returns the href attribute of the html element passed.
 fn get_attr(elt: &headless_chrome::Element, attr: &str) -> String {
     match elt.call_js_fn(&format!("function() {{ return this.getAttribute(\"{}\"); }}", attr), vec![], true).unwrap().value {
@@ -80,7 +148,7 @@ fn get_attr(elt: &headless_chrome::Element, attr: &str) -> String {
 /// Returns a vector of contests' repos that have div with class `contest_status` along with "contest-tile".
 /// It uses headless chromes to make a browser instance, navigate to the contests page and get the
 /// repos of the matching contests.
-async fn get_contests(contest_status: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+async fn get_contests(contest_status: &str) -> Result<Vec<Contest>, Box<dyn std::error::Error>> {
     let mut contests = Vec::new();
     let browser = Browser::default()?;
     let tab = browser.new_tab()?;
@@ -104,7 +172,13 @@ async fn get_contests(contest_status: &str) -> Result<Vec<String>, Box<dyn std::
                 for f in foot {
                     let href = get_attr(&f, "href");
                     if href.starts_with("https://github.com/") {
-                        contests.push(href);
+                        // A contest scoped to a specific audited revision carries it as
+                        // a `#<sha-or-tag>` fragment on the dropdown link.
+                        let (repo_url, pinned_ref) = match href.split_once('#') {
+                            Some((base, frag)) if !frag.is_empty() => (base.to_string(), Some(frag.to_string())),
+                            _ => (href, None),
+                        };
+                        contests.push(Contest { repo_url, pinned_ref });
                     }
                 }
             },
@@ -116,96 +190,313 @@ async fn get_contests(contest_status: &str) -> Result<Vec<String>, Box<dyn std::
 }
 
 /// Returns a vector containing the contest's repo name and a vector that contains the contracts in the repo along with their bytecode.
-async fn process(target_repos: &Vec<String>) -> Result<Vec<(String, Vec<(String, String)>)>, Box<dyn std::error::Error>> {
-    // let github_token = env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN must be set");
+///
+/// Repos are processed concurrently, each as its own `tokio` task. A shared
+/// `Arc<Semaphore>` caps how many of those tasks may be cloning/compiling at
+/// once (`MAX_CONCURRENT_OPS`), so the slow, disk/CPU-heavy steps stay
+/// bounded while each repo's top-level GitHub contents request can overlap
+/// freely.
+async fn process(target_repos: &Vec<Contest>, db: &Arc<Db>) -> Result<Vec<(String, Vec<(String, String)>)>, Box<dyn std::error::Error>> {
+    let repo_client = reqwest::Client::new();
+    let clone_permits = Arc::new(Semaphore::new(MAX_CONCURRENT_OPS));
+
+    let mut handles = Vec::new();
+    for contest in target_repos.clone() {
+        let repo_client = repo_client.clone();
+        let clone_permits = Arc::clone(&clone_permits);
+        let db = Arc::clone(db);
+        handles.push(tokio::spawn(async move {
+            process_repo(&contest, &repo_client, &clone_permits, &db).await
+        }));
+    }
 
     let mut all_results: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for handle in handles {
+        match handle.await {
+            Ok(Ok(Some(repo_result))) => all_results.push(repo_result),
+            Ok(Ok(None)) => {},
+            Ok(Err(e)) => eprintln!("Error processing repo: {}", e),
+            Err(e) => eprintln!("Repo task panicked: {}", e),
+        }
+    }
+    println!("ALL RESULT: {:#?}", all_results);
+    Ok(all_results)
+}
 
-    let repo_client = reqwest::Client::new();
-    for contest in target_repos {
-
-        let parts: Vec<&str> = contest.split('/').collect();
-        let (owner, repo) = (parts[3], parts[4]);
-        let repo_fetch_url = format!("https://github.com/{}/{}", &owner, &repo);
- 
-        let contents_url = format!("https://api.github.com/repos/{}/{}/contents", owner, repo);
-        println!("\nContest Repo: {:#?}", contents_url);
-        
-        let response = repo_client.get(&contents_url)
-            // .header("Authorization", format!("token {}", github_token))
-            .header(header::USER_AGENT, "Rust")
-            .send()
-            .await?;                            
-
-        if response.status().as_u16() > 400 {
-            println!("Repo not accessible. {:#?}", response.status().as_u16());
-            continue;
+/// Maximum number of exponential-backoff retries for a rate-limited request
+/// before `github_request` gives up and returns an error.
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+/// Longest we'll sleep to wait out a `X-RateLimit-Reset`, regardless of how
+/// far away it claims to be.
+const MAX_RATE_LIMIT_SLEEP: Duration = Duration::from_secs(3600);
+
+/// Issues a GET against the GitHub API, attaching `Authorization: token …`
+/// when `GITHUB_TOKEN` is set, and transparently retrying `403`/`429`
+/// responses: if `X-RateLimit-Remaining` has hit zero we sleep until
+/// `X-RateLimit-Reset` (capped at `MAX_RATE_LIMIT_SLEEP`), otherwise we back
+/// off exponentially (1s, 2s, 4s, …) up to `MAX_RATE_LIMIT_RETRIES` attempts.
+/// Any other `4xx`/`5xx` (e.g. a genuine `404`/`410`) is returned as an error
+/// immediately, so callers can tell "was throttled, now has an answer" apart
+/// from "repo is actually inaccessible".
+async fn github_request(repo_client: &reqwest::Client, url: &str) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+    let github_token = env::var("GITHUB_TOKEN").ok();
+    let mut attempt = 0;
+
+    loop {
+        let mut request = repo_client.get(url).header(header::USER_AGENT, "Rust");
+        if let Some(token) = &github_token {
+            request = request.header(header::AUTHORIZATION, format!("token {}", token));
         }
-        
-        // If repo already exists, delete it to clone latest
-        let repo_path_str = format!("./repos/{}", &repo);
-        let repo_path = Path::new(&repo_path_str);
-        if repo_path.exists() {
-            remove_dir_all(&repo_path_str);
-            let result = remove_dir_all(&repo_path_str).await;
-            if result.is_err() {
-                eprintln!("Failed to delete repository at {}: {}", repo_path_str, result.unwrap_err());
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+
+        if status == 403 || status == 429 {
+            let remaining: Option<u64> = response.headers().get("x-ratelimit-remaining")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok());
+
+            if remaining == Some(0) && attempt < MAX_RATE_LIMIT_RETRIES {
+                let reset: Option<u64> = response.headers().get("x-ratelimit-reset")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse().ok());
+                if let Some(reset_epoch) = reset {
+                    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+                    let wait = Duration::from_secs(reset_epoch.saturating_sub(now)).min(MAX_RATE_LIMIT_SLEEP);
+                    println!("Rate limit exhausted fetching {}, sleeping {:?} until reset.", url, wait);
+                    sleep(wait).await;
+                    attempt += 1;
+                    continue;
+                }
             }
+
+            if attempt < MAX_RATE_LIMIT_RETRIES {
+                let backoff = Duration::from_secs(1 << attempt);
+                println!("Rate limited ({}) fetching {}, retrying in {:?}.", status, url, backoff);
+                sleep(backoff).await;
+                attempt += 1;
+                continue;
+            }
+
+            return Err(Box::new(IoError::new(ErrorKind::Other, format!("rate limited fetching {} after {} retries", url, attempt))));
         }
-        // Clone repo locally and compile contracts
-        let repo_fetch = match Repository::clone(&repo_fetch_url, &repo_path_str) {
-            Ok(repo_fetch) => {
-                repo_fetch;
-                println!("Repo cloned. Attempting compilation.");
-
-                let output = Command::new("forge")
-                    .current_dir(&repo_path_str)
-                    .arg("compile")
-                    .output()
-                    .await?;  // executes the command                                    
-
-                if !output.status.success() {
-                    let err_msg = format!("forge build failed with status {}: {}", output.status, String::from_utf8_lossy(&output.stderr));
-                    // return Err(Box::new(IoError::new(ErrorKind::Other, err_msg)));
-                }
-            },
-            Err(e) => panic!("failed to clone: {}", e),
-        };
-
-        let body: String = response.text().await?;
-        
-        let contents: Vec<RepoContent> = serde_json::from_str(&body).unwrap();
-        let repo_results = process_contents(&contents, &repo_client, &owner, &repo).await?;
-        // println!("REPO RESULT: {:#?}", repo_results);
-        if !repo_results.is_empty() {
-            // If we found any contracts in this repo, add it to the overall results.
-            all_results.push((repo.to_string(), repo_results));
+
+        if status >= 400 {
+            return Err(Box::new(IoError::new(ErrorKind::Other, format!("request to {} failed with status {}", url, status))));
+        }
+
+        return Ok(response);
+    }
+}
+
+/// Clones `repo_fetch_url` into `repo_path_str` as a shallow (depth-1)
+/// checkout of the default branch, then -- if `pinned_ref` names the
+/// commit/tag the contest was actually scoped to -- fetches and checks that
+/// revision out, so the bytecode reflects exactly the audited state rather
+/// than wherever the default branch has since moved to.
+fn clone_repo(repo_fetch_url: &str, repo_path_str: &str, pinned_ref: Option<&str>) -> Result<(), git2::Error> {
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.depth(1);
+
+    let repo = RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .clone(repo_fetch_url, Path::new(repo_path_str))?;
+
+    if let Some(pinned_ref) = pinned_ref {
+        // The shallow clone above only has the default branch tip, so the
+        // pinned revision needs its own (also depth-1) fetch before it can
+        // be resolved and checked out.
+        let mut remote = repo.find_remote("origin")?;
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.depth(1);
+        remote.fetch(&[pinned_ref], Some(&mut fetch_options), None)?;
+
+        let object = repo.revparse_single(pinned_ref)?;
+        repo.checkout_tree(&object, None)?;
+        repo.set_head_detached(object.id())?;
+    }
+
+    Ok(())
+}
+
+/// Clones, compiles and inspects a single contest repo, guarding the clone,
+/// `forge compile` and `forge inspect` (via `process_contents`/`get_bytecode`)
+/// steps behind a single permit from `clone_permits` so at most
+/// `MAX_CONCURRENT_OPS` of these heavyweight operations run at once across
+/// all repos. Returns `None` if the repo is inaccessible or has no matching
+/// contracts.
+async fn process_repo(contest: &Contest, repo_client: &reqwest::Client, clone_permits: &Arc<Semaphore>, db: &Arc<Db>) -> Result<Option<(String, Vec<(String, String)>)>, Box<dyn std::error::Error + Send + Sync>> {
+    let parts: Vec<&str> = contest.repo_url.split('/').collect();
+    let (owner, repo) = (parts[3], parts[4]);
+    let repo_fetch_url = format!("https://github.com/{}/{}", &owner, &repo);
+
+    let contents_url = format!("https://api.github.com/repos/{}/{}/contents", owner, repo);
+    println!("\nContest Repo: {:#?}", contents_url);
+
+    let response = match github_request(repo_client, &contents_url).await {
+        Ok(response) => response,
+        Err(e) => {
+            println!("Repo not accessible: {}", e);
+            return Ok(None);
         }
-        println!("ALL RESULT: {:#?}", all_results);
+    };
 
-        // Delete the cloned repo
+    // Each task gets its own working directory -- owner/repo alone isn't
+    // unique enough (see NEXT_CLONE_ID doc comment) now that repos are
+    // cloned/compiled concurrently.
+    let clone_id = NEXT_CLONE_ID.fetch_add(1, Ordering::Relaxed);
+    let repo_path_str = format!("./repos/{}-{}-{}", owner, repo, clone_id);
+    let repo_path = Path::new(&repo_path_str);
+    if repo_path.exists() {
         let result = remove_dir_all(&repo_path_str).await;
         if result.is_err() {
             eprintln!("Failed to delete repository at {}: {}", repo_path_str, result.unwrap_err());
         }
     }
-    Ok(all_results)
+
+    // Clone repo locally, compile contracts, and inspect each one. All three
+    // are expensive enough (network + disk, then a full compiler invocation
+    // per repo, then a `forge inspect` invocation per contract) that we hold
+    // a single permit across the whole span rather than just the clone.
+    // `clone_repo`'s git2 calls are synchronous network+disk I/O, so they run
+    // on the blocking thread pool rather than tying up a tokio worker thread
+    // for their whole duration.
+    let permit = clone_permits.acquire().await?;
+    let clone_url = repo_fetch_url.clone();
+    let clone_path = repo_path_str.clone();
+    let clone_pinned_ref = contest.pinned_ref.clone();
+    if let Err(e) = tokio::task::spawn_blocking(move || {
+        clone_repo(&clone_url, &clone_path, clone_pinned_ref.as_deref())
+    }).await? {
+        // A flaky fetch of the pinned revision (or of the repo itself) is a
+        // routine, recoverable failure over a full contest sweep -- treat it
+        // like every other "this repo didn't work out" case rather than
+        // taking down the whole task.
+        eprintln!("Failed to clone {}: {}", repo_fetch_url, e);
+        drop(permit);
+        let result = remove_dir_all(&repo_path_str).await;
+        if result.is_err() {
+            eprintln!("Failed to delete repository at {}: {}", repo_path_str, result.unwrap_err());
+        }
+        return Ok(None);
+    }
+    println!("Repo cloned. Attempting compilation.");
+
+    let output = Command::new("forge")
+        .current_dir(&repo_path_str)
+        .arg("compile")
+        .output()
+        .await?;  // executes the command
+
+    if !output.status.success() {
+        eprintln!("forge build failed with status {}: {}", output.status, String::from_utf8_lossy(&output.stderr));
+    }
+
+    let body: String = response.text().await?;
+
+    let contents: Vec<RepoContent> = serde_json::from_str(&body).unwrap();
+    // See Db's doc comment for why this is the full "owner/repo" rather
+    // than the bare repo name.
+    let repo_key = format!("{}/{}", owner, repo);
+    let ctx = RepoCtx { owner, repo, repo_key: &repo_key, repo_dir: &repo_path_str };
+    let mut visited_paths: Vec<String> = Vec::new();
+    // `process_contents` recurses into `get_bytecode`, which shells out to
+    // `forge inspect` per contract -- another heavyweight `forge` invocation,
+    // so the permit stays held through this call too, not just `forge compile`.
+    let repo_results = process_contents(&contents, repo_client, &ctx, db, &mut visited_paths).await?;
+    drop(permit);
+    // println!("REPO RESULT: {:#?}", repo_results);
+    report_removed_contracts(ctx.repo_key, db, &visited_paths);
+
+    // Delete the cloned repo
+    let result = remove_dir_all(&repo_path_str).await;
+    if result.is_err() {
+        eprintln!("Failed to delete repository at {}: {}", repo_path_str, result.unwrap_err());
+    }
+
+    if repo_results.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some((repo.to_string(), repo_results)))
+    }
 }
 
-#[async_recursion]
-async fn process_contents(contents: &Vec<RepoContent>, repo_client: &reqwest::Client, owner: &str, repo: &str) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
-    // let github_token = env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN must be set");
+/// Labels a freshly inspected contract as added/changed/unchanged relative to
+/// `previous_bytecode` (the last bytecode recorded for this path, if any),
+/// printing a unified diff for the "changed" case so auditors can see
+/// exactly what moved in the compiled output between contest revisions.
+fn report_bytecode_diff(path: &str, previous_bytecode: Option<&str>, bytecode: &str) {
+    match previous_bytecode {
+        None => println!("{} added.", path),
+        Some(previous) if previous == bytecode => println!("{} unchanged.", path),
+        Some(previous) => {
+            let patch = create_patch(previous, bytecode);
+            println!("{} changed:\n{}", path, patch);
+        }
+    }
+}
+
+/// Everything `process_contents`/`get_bytecode` need to know about the repo
+/// currently being processed: `owner`/`repo` for GitHub API calls, `repo_key`
+/// for SQLite lookups, and `repo_dir` -- the unique local directory it was
+/// actually cloned into -- for `forge` invocations.
+struct RepoCtx<'a> {
+    owner: &'a str,
+    repo: &'a str,
+    repo_key: &'a str,
+    repo_dir: &'a str,
+}
+
+/// Prints a notice for every contract path this repo had bytecode recorded
+/// for in a previous run but that wasn't visited in the current one, i.e.
+/// it was removed (deleted or renamed) from the repo.
+fn report_removed_contracts(repo: &str, db: &Db, visited_paths: &[String]) {
+    let known_paths = match db.known_paths(repo) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("Failed to look up known contract paths for {}: {}", repo, e);
+            return;
+        }
+    };
+    for path in known_paths {
+        if !visited_paths.contains(&path) {
+            println!("{} removed.", path);
+        }
+    }
+}
 
+#[async_recursion]
+async fn process_contents(contents: &Vec<RepoContent>, repo_client: &reqwest::Client, ctx: &RepoCtx, db: &Arc<Db>, visited_paths: &mut Vec<String>) -> Result<Vec<(String, String)>, Box<dyn std::error::Error + Send + Sync>> {
     let mut repo_results: Vec<(String, String)> = Vec::new();
     for content in contents {
         match content._type.as_deref() {
             Some("file") => {
                 if let Some(name) = &content.name {
                     if name.ends_with(".sol") && !name.ends_with(".t.sol") && !name.ends_with(".s.sol") && !name.contains("Test") {
-                        if let Some(filename) = content.name.as_ref() {
-                            match get_bytecode(&filename, &repo).await {
+                        if let (Some(filename), Some(path), Some(sha)) = (content.name.as_ref(), content.path.as_ref(), content.sha.as_ref()) {
+                            visited_paths.push(path.clone());
+
+                            let cached = db.cached_bytecode(ctx.repo_key, path, sha).unwrap_or_else(|e| {
+                                eprintln!("Bytecode cache lookup failed for {}: {}", path, e);
+                                None
+                            });
+                            if let Some(bytecode) = cached {
+                                println!("{} unchanged (cached bytecode).", path);
+                                repo_results.push((filename.clone(), bytecode));
+                                continue;
+                            }
+
+                            let previous_bytecode = db.latest_bytecode(ctx.repo_key, path).unwrap_or_else(|e| {
+                                eprintln!("Bytecode history lookup failed for {}: {}", path, e);
+                                None
+                            });
+
+                            match get_bytecode(&filename, ctx.repo_dir).await {
                                 Ok(bytecode) => {
-                                    println!("Bytecode exists for: {}", filename);
+                                    report_bytecode_diff(path, previous_bytecode.as_deref(), &bytecode);
+                                    if let Err(e) = db.record_bytecode(ctx.repo_key, path, sha, &bytecode) {
+                                        eprintln!("Failed to cache bytecode for {}: {}", path, e);
+                                    }
                                     repo_results.push((filename.clone(), bytecode));
                                 }
                                 Err(e) => {
@@ -222,14 +513,10 @@ async fn process_contents(contents: &Vec<RepoContent>, repo_client: &reqwest::Cl
             Some("dir") => {
                 // this is a directory, need to fetch its contents and process them
                 if let Some(path) = &content.path {
-                    let dir_url = format!("https://api.github.com/repos/{}/{}/contents/{}", owner, repo, path);
-                    let response = repo_client.get(&dir_url)
-                        // .header(header::AUTHORIZATION, format!("token {}", github_token))
-                        .header("User-Agent", "Rust")
-                        .send()
-                        .await?;
+                    let dir_url = format!("https://api.github.com/repos/{}/{}/contents/{}", ctx.owner, ctx.repo, path);
+                    let response = github_request(repo_client, &dir_url).await?;
                     let dir_contents: Vec<RepoContent> = response.json().await?;
-                    let mut dir_results = process_contents(&dir_contents, repo_client, owner, repo).await?;
+                    let mut dir_results = process_contents(&dir_contents, repo_client, ctx, db, visited_paths).await?;
                     repo_results.append(&mut dir_results);
                 }
             },
@@ -252,13 +539,13 @@ fn get_pragma_version(source: &str) -> Option<String> {
     None
 }
 
-/// Returns the bytecode for a given contract in a given repo.
-async fn get_bytecode(original_file_name: &str, repo: &str) -> Result<String, Box<dyn std::error::Error>> {
+/// Returns the bytecode for a given contract, invoking `forge` in `repo_dir`
+/// -- the unique local directory the repo was actually cloned into.
+async fn get_bytecode(original_file_name: &str, repo_dir: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     let file_name = original_file_name.strip_suffix(".sol").unwrap();
-    let repo_path = format!("./repos/{}", &repo);
-    
+
     let output = Command::new("forge")
-        .current_dir(&repo_path)
+        .current_dir(repo_dir)
         .arg("inspect")
         .arg(&file_name)
         .arg("bytecode")
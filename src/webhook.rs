@@ -0,0 +1,164 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use crate::dbctx::Db;
+use crate::{process_repo, Contest};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Shared state for the webhook server: the same repo client / clone
+/// semaphore / SQLite cache the one-shot scrape uses, plus the pre-shared
+/// keys accepted for `X-Hub-Signature-256` verification.
+pub struct WebhookState {
+    pub repo_client: reqwest::Client,
+    pub clone_permits: Arc<Semaphore>,
+    pub db: Arc<Db>,
+    pub webhook_secrets: Vec<String>,
+}
+
+/// Starts the axum-based webhook server on `addr`, re-auditing a repo's
+/// bytecode whenever GitHub delivers a verified `push` event for it.
+pub async fn run(addr: &str, state: WebhookState) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let app = Router::new()
+        .route("/webhook", post(handle_webhook))
+        .with_state(Arc::new(state));
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("Webhook server listening on {}", addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Handles a single GitHub push webhook delivery: verifies the HMAC
+/// signature against every configured pre-shared key, then kicks off a
+/// clone -> `forge compile` -> `get_bytecode` pass for just the pushed repo.
+async fn handle_webhook(State(state): State<Arc<WebhookState>>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    let signature = match headers.get("x-hub-signature-256").and_then(|v| v.to_str().ok()) {
+        Some(sig) => sig,
+        None => return StatusCode::UNAUTHORIZED,
+    };
+
+    if !state.webhook_secrets.iter().any(|psk| verify_signature(psk, &body, signature)) {
+        eprintln!("Webhook signature verification failed.");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let payload: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Failed to parse webhook payload: {}", e);
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+
+    let full_name = match payload["repository"]["full_name"].as_str() {
+        Some(name) => name.to_string(),
+        None => return StatusCode::BAD_REQUEST,
+    };
+    // `process_repo` splits this on '/' and indexes straight into the
+    // owner/repo segments, so a malformed `full_name` must be rejected here
+    // rather than panicking the spawned task below.
+    match full_name.split('/').collect::<Vec<_>>().as_slice() {
+        [owner, repo] if !owner.is_empty() && !repo.is_empty() => {}
+        _ => {
+            eprintln!("Rejecting webhook with malformed repository.full_name: {:?}", full_name);
+            return StatusCode::BAD_REQUEST;
+        }
+    }
+    let pushed_ref = payload["ref"].as_str().unwrap_or("refs/heads/unknown").to_string();
+    let pushed_commit = payload["after"].as_str().map(|s| s.to_string());
+
+    println!("Verified push to {} ({}), re-auditing.", full_name, pushed_ref);
+
+    let contest = Contest {
+        repo_url: format!("https://github.com/{}", full_name),
+        pinned_ref: pushed_commit,
+    };
+    tokio::spawn(async move {
+        match process_repo(&contest, &state.repo_client, &state.clone_permits, &state.db).await {
+            Ok(Some((repo, results))) => println!("Re-audited {}: {} contracts", repo, results.len()),
+            Ok(None) => println!("Re-audit of {} found no matching contracts.", full_name),
+            Err(e) => eprintln!("Re-audit of {} failed: {}", full_name, e),
+        }
+    });
+
+    StatusCode::OK
+}
+
+/// Recomputes an HMAC-SHA256 over `body` using `psk` and constant-time
+/// compares it against the `sha256=<hex>` value GitHub sends in
+/// `X-Hub-Signature-256`.
+fn verify_signature(psk: &str, body: &[u8], signature_header: &str) -> bool {
+    let expected_hex = match signature_header.strip_prefix("sha256=") {
+        Some(hex) => hex,
+        None => return false,
+    };
+    let expected = match hex_to_bytes(expected_hex) {
+        Some(bytes) => bytes,
+        None => return false,
+    };
+
+    let mut mac = match HmacSha256::new_from_slice(psk.as_bytes()) {
+        Ok(mac) => mac,
+        Err(_) => return false,
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    let bytes = hex.as_bytes();
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    bytes
+        .chunks(2)
+        .map(|pair| {
+            let hi = (pair[0] as char).to_digit(16)?;
+            let lo = (pair[1] as char).to_digit(16)?;
+            Some((hi as u8) << 4 | lo as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_to_bytes_decodes_valid_hex() {
+        assert_eq!(hex_to_bytes("00ff"), Some(vec![0x00, 0xff]));
+        assert_eq!(hex_to_bytes(""), Some(vec![]));
+    }
+
+    #[test]
+    fn hex_to_bytes_rejects_odd_length() {
+        assert_eq!(hex_to_bytes("abc"), None);
+    }
+
+    #[test]
+    fn hex_to_bytes_rejects_non_hex_ascii() {
+        assert_eq!(hex_to_bytes("zz"), None);
+    }
+
+    #[test]
+    fn hex_to_bytes_rejects_non_ascii_without_panicking() {
+        // A naive `&hex[i..i + 2]` byte-range index panics here because
+        // 'é' is a 2-byte UTF-8 char whose boundary falls mid-string.
+        assert_eq!(hex_to_bytes("aéa"), None);
+    }
+
+    #[test]
+    fn verify_signature_rejects_non_ascii_signature_without_panicking() {
+        assert!(!verify_signature("secret", b"payload", "sha256=aéa"));
+    }
+}